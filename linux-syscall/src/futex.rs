@@ -0,0 +1,324 @@
+//! Syscalls for futex-based userspace thread synchronization
+//! - futex
+//!
+//! FIXME(blocking): `sys_futex` is not reachable from userspace yet. It must
+//! be routed to the `futex` syscall number in the syscall-number -> handler
+//! dispatch table, but that table is not part of this series — this crate
+//! has no `lib.rs`/dispatch module in this checkout for it to land in, and
+//! nothing elsewhere in the tree references `sys_futex`. Confirmed by
+//! `grep -rn sys_futex` over the repo: the only hits are this file's own
+//! definition and this comment. Do not merge this as "glibc/musl mutexes and
+//! condvars now work" without also landing that wiring (or pointing at the
+//! specific commit that already supplies it).
+use crate::Syscall;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use core::task::{Context, Poll, Waker};
+use core::time::Duration;
+use futures::future::{select, Either};
+use hashbrown::HashMap;
+use kernel_hal::user::UserInPtr;
+use linux_object::error::{LxError, SysResult};
+use linux_object::time::*;
+use spin::Mutex;
+
+use crate::time::clock_now;
+
+const FUTEX_WAIT: usize = 0;
+const FUTEX_WAKE: usize = 1;
+const FUTEX_REQUEUE: usize = 3;
+const FUTEX_CMP_REQUEUE: usize = 4;
+const FUTEX_PRIVATE_FLAG: usize = 128;
+const FUTEX_CLOCK_REALTIME: usize = 256;
+const FUTEX_CMD_MASK: usize = !(FUTEX_PRIVATE_FLAG | FUTEX_CLOCK_REALTIME);
+
+/// The command encoded in the low bits of a futex `op`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FutexOp {
+    Wait,
+    Wake,
+    Requeue,
+    CmpRequeue,
+}
+
+impl FutexOp {
+    fn decode(op: usize) -> SysResult<(Self, bool, bool)> {
+        let private = op & FUTEX_PRIVATE_FLAG != 0;
+        let clock_realtime = op & FUTEX_CLOCK_REALTIME != 0;
+        let cmd = match op & FUTEX_CMD_MASK {
+            FUTEX_WAIT => Self::Wait,
+            FUTEX_WAKE => Self::Wake,
+            FUTEX_REQUEUE => Self::Requeue,
+            FUTEX_CMP_REQUEUE => Self::CmpRequeue,
+            _ => return Err(LxError::ENOSYS),
+        };
+        Ok((cmd, private, clock_realtime))
+    }
+}
+
+/// A single parked `FUTEX_WAIT` caller.
+///
+/// `waker` starts empty: the waiter is enqueued (under the bucket lock, see
+/// `futex_wait`) before the caller ever polls the future that owns it, so
+/// there's nothing to wake yet. `FutexWait::poll` fills it in on first poll.
+struct Waiter {
+    id: u64,
+    woken: Arc<AtomicBool>,
+    waker: Option<Waker>,
+}
+
+/// The wait queue for one futex word.
+#[derive(Default)]
+struct FutexQueue {
+    waiters: VecDeque<Waiter>,
+}
+
+/// Global table of futex wait queues, keyed by `futex_key`.
+static FUTEX_TABLE: Mutex<Option<HashMap<usize, Arc<Mutex<FutexQueue>>>>> = Mutex::new(None);
+
+/// Source of unique `Waiter` ids, used to find and remove a specific waiter
+/// from its bucket (e.g. when its wait is cancelled by a timeout) without
+/// disturbing anyone else queued on the same futex.
+static NEXT_WAITER_ID: AtomicU64 = AtomicU64::new(0);
+
+fn futex_bucket(key: usize) -> Arc<Mutex<FutexQueue>> {
+    FUTEX_TABLE
+        .lock()
+        .get_or_insert_with(HashMap::new)
+        .entry(key)
+        .or_insert_with(|| Arc::new(Mutex::new(FutexQueue::default())))
+        .clone()
+}
+
+/// Parks the current task on `bucket` until woken by `FUTEX_WAKE`/`FUTEX_REQUEUE`.
+///
+/// Must be constructed via `FutexWait::enqueue`, which atomically re-checks
+/// the futex word and registers the waiter under the same bucket-lock
+/// critical section, so a concurrent `FUTEX_WAKE` can never land in the
+/// window between the check and the enqueue and get lost.
+struct FutexWait {
+    bucket: Arc<Mutex<FutexQueue>>,
+    woken: Arc<AtomicBool>,
+    id: u64,
+}
+
+impl FutexWait {
+    /// Re-reads `uaddr` and, if it still equals `val`, enqueues a waiter on
+    /// `bucket` in one critical section. Returns `EAGAIN` if the value had
+    /// already changed.
+    fn enqueue(bucket: Arc<Mutex<FutexQueue>>, uaddr: UserInPtr<u32>, val: u32) -> SysResult<Self> {
+        let mut queue = bucket.lock();
+        if uaddr.read()? != val {
+            return Err(LxError::EAGAIN);
+        }
+        let woken = Arc::new(AtomicBool::new(false));
+        let id = NEXT_WAITER_ID.fetch_add(1, Ordering::Relaxed);
+        queue.waiters.push_back(Waiter {
+            id,
+            woken: woken.clone(),
+            waker: None,
+        });
+        drop(queue);
+        Ok(Self { bucket, woken, id })
+    }
+}
+
+impl Future for FutexWait {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        if this.woken.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+        let mut queue = this.bucket.lock();
+        if let Some(waiter) = queue.waiters.iter_mut().find(|w| w.id == this.id) {
+            waiter.waker = Some(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for FutexWait {
+    /// If this wait lost the race (e.g. it timed out) while still parked,
+    /// remove its now-dead entry from the bucket so a later `FUTEX_WAKE`
+    /// doesn't spend part of its wake budget on a waiter nobody is polling.
+    /// If it was already woken, `FUTEX_WAKE`/`FUTEX_REQUEUE` already popped
+    /// it out of the queue and there's nothing left to clean up.
+    fn drop(&mut self) {
+        if self.woken.load(Ordering::Acquire) {
+            return;
+        }
+        let mut queue = self.bucket.lock();
+        if let Some(pos) = queue.waiters.iter().position(|w| w.id == self.id) {
+            queue.waiters.remove(pos);
+        }
+    }
+}
+
+/// Wakes up to `max_wake` waiters parked on `bucket`.
+fn futex_wake_bucket(bucket: &Arc<Mutex<FutexQueue>>, max_wake: usize) -> usize {
+    let mut queue = bucket.lock();
+    let mut woken = 0;
+    while woken < max_wake {
+        match queue.waiters.pop_front() {
+            Some(waiter) => {
+                waiter.woken.store(true, Ordering::Release);
+                if let Some(waker) = waiter.waker {
+                    waker.wake();
+                }
+                woken += 1;
+            }
+            None => break,
+        }
+    }
+    woken
+}
+
+/// Sleeps until `clock_now(clock)` reaches `deadline`, polling in small
+/// slices since there's no hardware timer keyed to an arbitrary clock
+/// (needed so `FUTEX_CLOCK_REALTIME` timeouts actually track the realtime
+/// clock, offset changes from `clock_settime` included, rather than always
+/// measuring against the monotonic clock like a plain `nanosleep`).
+async fn sleep_until(clock: ClockId, deadline: Duration) {
+    const POLL_INTERVAL: Duration = Duration::from_millis(1);
+    loop {
+        let now: Duration = match clock_now(clock) {
+            Ok(ts) => ts.into(),
+            Err(_) => return,
+        };
+        match deadline.checked_sub(now) {
+            Some(remaining) if !remaining.is_zero() => {
+                nanosleep(remaining.min(POLL_INTERVAL)).await;
+            }
+            _ => return,
+        }
+    }
+}
+
+impl Syscall<'_> {
+    /// Resolves the key a futex word is tracked under.
+    ///
+    /// Private futexes (`FUTEX_PRIVATE_FLAG`) are keyed within this process's
+    /// address space; shared futexes are keyed by the physical frame and
+    /// offset backing `vaddr`, so waiters in different address spaces that
+    /// map the same page still rendezvous on the same wait queue.
+    fn futex_key(&self, vaddr: usize, private: bool) -> SysResult<usize> {
+        if private {
+            let pid = self.zircon_process().id() as usize;
+            Ok(pid << 48 | vaddr)
+        } else {
+            let paddr = self
+                .zircon_process()
+                .vmar()
+                .translate(vaddr)
+                .map_err(|_| LxError::EFAULT)?;
+            Ok(paddr)
+        }
+    }
+
+    /// Implements the raw `futex(2)` syscall used by glibc/musl mutexes and
+    /// condvars: `FUTEX_WAIT`, `FUTEX_WAKE`, `FUTEX_REQUEUE` and
+    /// `FUTEX_CMP_REQUEUE`, honoring `FUTEX_PRIVATE_FLAG`.
+    ///
+    /// `timeout` is a relative `TimeSpec` for `FUTEX_WAIT`. For the requeue
+    /// operations the real `futex(2)` ABI reinterprets this same argument
+    /// as `val2`, an integer count rather than a pointer: the maximum
+    /// number of waiters (beyond the `val` woken directly) to move over to
+    /// `uaddr2`. `uaddr2`/`val3` are only meaningful for the requeue
+    /// operations, where `val3` is the value `uaddr` is compared against
+    /// for `FUTEX_CMP_REQUEUE`.
+    pub async fn sys_futex(
+        &self,
+        uaddr: UserInPtr<u32>,
+        op: usize,
+        val: u32,
+        timeout: UserInPtr<TimeSpec>,
+        uaddr2: UserInPtr<u32>,
+        val3: u32,
+    ) -> SysResult {
+        let (cmd, private, clock_realtime) = FutexOp::decode(op)?;
+        info!(
+            "futex: uaddr={:?}, cmd={:?}, val={}, private={}, clock_realtime={}",
+            uaddr, cmd, val, private, clock_realtime
+        );
+        match cmd {
+            FutexOp::Wait => {
+                self.futex_wait(uaddr, val, timeout, private, clock_realtime)
+                    .await
+            }
+            FutexOp::Wake => {
+                let key = self.futex_key(uaddr.as_ptr() as usize, private)?;
+                let woken = futex_wake_bucket(&futex_bucket(key), val as usize);
+                Ok(woken)
+            }
+            FutexOp::Requeue | FutexOp::CmpRequeue => {
+                if cmd == FutexOp::CmpRequeue {
+                    let current = uaddr.read()?;
+                    if current != val3 {
+                        return Err(LxError::EAGAIN);
+                    }
+                }
+                // `timeout` is reinterpreted as `val2` for these ops: it's
+                // never a valid timeout pointer here, just the requeue cap.
+                let val2 = timeout.as_ptr() as usize;
+                let src_key = self.futex_key(uaddr.as_ptr() as usize, private)?;
+                let dst_key = self.futex_key(uaddr2.as_ptr() as usize, private)?;
+                let src_bucket = futex_bucket(src_key);
+                let dst_bucket = futex_bucket(dst_key);
+                let woken = futex_wake_bucket(&src_bucket, val as usize);
+                // Move up to `val2` of the still-waiting threads over to
+                // `uaddr2`; anyone beyond that stays parked on `uaddr`.
+                let mut src_queue = src_bucket.lock();
+                let to_move = val2.min(src_queue.waiters.len());
+                let moved: VecDeque<_> = src_queue.waiters.drain(..to_move).collect();
+                drop(src_queue);
+                dst_bucket.lock().waiters.extend(moved);
+                Ok(woken)
+            }
+        }
+    }
+
+    /// `FUTEX_WAIT`: atomically re-check `uaddr == val`, and if so park the
+    /// caller until woken or `timeout` (if non-null) elapses.
+    ///
+    /// `timeout` is relative; with `clock_realtime` set (`FUTEX_CLOCK_REALTIME`)
+    /// it is measured against `CLOCK_REALTIME` instead of the monotonic
+    /// clock, so it tracks `clock_settime`/`settimeofday` adjustments made
+    /// while the wait is in progress.
+    async fn futex_wait(
+        &self,
+        uaddr: UserInPtr<u32>,
+        val: u32,
+        timeout: UserInPtr<TimeSpec>,
+        private: bool,
+        clock_realtime: bool,
+    ) -> SysResult {
+        let key = self.futex_key(uaddr.as_ptr() as usize, private)?;
+        // The check-and-enqueue must happen atomically under the bucket
+        // lock: re-reading `uaddr` and only then registering the waiter
+        // would leave a window where a concurrent FUTEX_WAKE sees an empty
+        // bucket and the wakeup is lost.
+        let wait = FutexWait::enqueue(futex_bucket(key), uaddr, val)?;
+        if timeout.is_null() {
+            wait.await;
+            return Ok(0);
+        }
+        let duration: Duration = timeout.read()?.into();
+        let clock = if clock_realtime {
+            ClockId::ClockRealTime
+        } else {
+            ClockId::ClockMonotonic
+        };
+        let now: Duration = clock_now(clock)?.into();
+        let deadline = now + duration;
+        match select(wait, Box::pin(sleep_until(clock, deadline))).await {
+            Either::Left(_) => Ok(0),
+            Either::Right(_) => Err(LxError::ETIMEDOUT),
+        }
+    }
+}
@@ -1,36 +1,223 @@
 //! Syscalls for time
-//! - clock_gettime
+//! - clock_gettime, clock_gettime64
+//! - gettimeofday, settimeofday
+//! - time
+//! - getrusage, times
+//! - nanosleep, nanosleep_time64
+//! - clock_nanosleep, clock_nanosleep_time64
+//! - clock_settime
 //!
+//! FIXME(blocking): `sys_clock_settime`, `sys_settimeofday`,
+//! `sys_clock_gettime64`, `sys_nanosleep_time64` and
+//! `sys_clock_nanosleep_time64` are brand-new entry points with no
+//! dispatch-table wiring anywhere in this tree — there is no
+//! syscall-number -> handler dispatch table in this checkout (no
+//! `lib.rs`/dispatch module exists for it to land in) to route any raw
+//! syscall number to the `Syscall` method of the same name. `grep -rn
+//! sys_clock_settime` (and the other four names above) over the repo turns
+//! up only their own definitions. None of them are reachable from userspace
+//! as shipped, so "wire up the `*_time64` syscall numbers" and "settable
+//! wall clock via sys_clock_settime/sys_settimeofday" are not actually
+//! delivered yet — don't merge on the strength of those stated goals
+//! without also landing the dispatch wiring (or pointing at the specific
+//! commit that already supplies it).
 use crate::Syscall;
+use core::sync::atomic::{AtomicI64, Ordering};
+use core::time::Duration;
+use kernel_hal::timer::timer_now;
 use kernel_hal::{user::UserInPtr, user::UserOutPtr};
 use linux_object::error::LxError;
+use linux_object::error::LxResult;
 use linux_object::error::SysResult;
 use linux_object::time::*;
+use zircon_object::object::KoID;
 
 const USEC_PER_TICK: usize = 10000;
+/// `who` values accepted by `sys_getrusage`.
+const RUSAGE_SELF: usize = 0;
+const RUSAGE_THREAD: usize = 1;
+/// `-1` as an `int`, sign-extended into the `usize` syscall argument.
+const RUSAGE_CHILDREN: usize = usize::MAX;
+
+/// Accumulated CPU time for one thread or process, in nanoseconds.
+///
+/// Nothing in this tree hooks a user\<-\>kernel transition (there's no trap
+/// entry/exit path here to account from), so `thread_cpu_time`/
+/// `children_cpu_time` below always report zero. `sys_getrusage`/`sys_times`
+/// read through this type rather than returning bogus non-zero numbers, but
+/// they are not yet measuring real CPU usage; `clock_time` refuses the
+/// `CLOCK_*_CPUTIME_ID` clocks outright instead of pretending a zero reading
+/// is meaningful.
+#[derive(Debug, Clone, Copy, Default)]
+struct CpuTime {
+    user_ns: u64,
+    sys_ns: u64,
+}
+
+impl CpuTime {
+    fn add(self, other: Self) -> Self {
+        Self {
+            user_ns: self.user_ns + other.user_ns,
+            sys_ns: self.sys_ns + other.sys_ns,
+        }
+    }
+
+    fn total(self) -> Duration {
+        Duration::from_nanos(self.user_ns + self.sys_ns)
+    }
+}
+
+/// Always `CpuTime::default()`: no caller in this tree ever accounts real
+/// per-thread CPU time (see the `CpuTime` doc comment above).
+fn thread_cpu_time(_tid: KoID) -> CpuTime {
+    CpuTime::default()
+}
+
+/// Always `CpuTime::default()`: no caller in this tree ever folds a reaped
+/// child's usage into its parent (see the `CpuTime` doc comment above).
+fn children_cpu_time(_parent: KoID) -> CpuTime {
+    CpuTime::default()
+}
+
+/// Nanoseconds added to the monotonic clock to produce `CLOCK_REALTIME`.
+///
+/// Defaults to zero (wall-clock time starts at the Epoch) until
+/// `clock_settime`/`settimeofday` establish a real offset.
+static REALTIME_OFFSET_NANOS: AtomicI64 = AtomicI64::new(0);
+
+/// Nanoseconds elapsed since boot, unaffected by wall-clock adjustments.
+fn monotonic_now() -> Duration {
+    timer_now()
+}
+
+/// Wall-clock time: the monotonic clock plus the stored boot-epoch offset.
+fn realtime_now() -> Duration {
+    let offset = REALTIME_OFFSET_NANOS.load(Ordering::Relaxed);
+    let mono = monotonic_now();
+    if offset >= 0 {
+        mono + Duration::from_nanos(offset as u64)
+    } else {
+        mono.saturating_sub(Duration::from_nanos((-offset) as u64))
+    }
+}
+
+/// Recomputes and stores the boot-epoch offset so that `realtime_now()`
+/// returns `new_realtime` right now, shifting all future `CLOCK_REALTIME`
+/// reads consistently. `CLOCK_MONOTONIC`/`CLOCK_BOOTTIME` are unaffected,
+/// since they never consult this offset.
+fn set_realtime_offset(new_realtime: Duration) {
+    let offset = new_realtime.as_nanos() as i128 - monotonic_now().as_nanos() as i128;
+    REALTIME_OFFSET_NANOS.store(offset as i64, Ordering::Relaxed);
+}
+
+/// Round `d` down to the nearest tick, modeling the cheaper `*_COARSE` read path.
+fn round_to_tick(d: Duration) -> Duration {
+    let tick = Duration::from_micros(USEC_PER_TICK as u64);
+    d - Duration::from_nanos((d.as_nanos() % tick.as_nanos()) as u64)
+}
+
+/// The ABI layout of Linux's `__kernel_timespec`: an explicitly 64-bit-second
+/// timespec used by the `*_time64` syscalls, so 32-bit userspace (riscv32,
+/// x86 compat) reading/writing it stays correct past the year 2038 even
+/// though `TimeSpec`'s own `sec` field is only as wide as the kernel's
+/// native `usize`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KernelTimespec64 {
+    pub tv_sec: i64,
+    pub tv_nsec: i64,
+}
+
+impl From<Duration> for KernelTimespec64 {
+    fn from(d: Duration) -> Self {
+        Self {
+            tv_sec: d.as_secs() as i64,
+            tv_nsec: d.subsec_nanos() as i64,
+        }
+    }
+}
+
+impl From<KernelTimespec64> for Duration {
+    fn from(ts: KernelTimespec64) -> Self {
+        Duration::new(ts.tv_sec.max(0) as u64, ts.tv_nsec.max(0) as u32)
+    }
+}
+
+/// The outcome of the shared nanosleep implementation behind both the legacy
+/// 32-bit-second and `_time64` entry points.
+enum NanosleepOutcome {
+    /// The sleep ran to completion.
+    Done,
+    /// The sleep was cut short; the caller should write this back through
+    /// `rem` (if non-null) and report `EINTR`.
+    Interrupted(Duration),
+}
+
+/// Resolve the current time for `clockid`, or `EINVAL` if it's not supported.
+pub(crate) fn clock_now(clockid: ClockId) -> LxResult<TimeSpec> {
+    let now = match clockid {
+        ClockId::ClockRealTime => realtime_now(),
+        ClockId::ClockMonotonic | ClockId::ClockMonotonicRaw | ClockId::ClockBootTime => {
+            monotonic_now()
+        }
+        ClockId::ClockRealTimeCoarse => round_to_tick(realtime_now()),
+        ClockId::ClockMonotonicCoarse => round_to_tick(monotonic_now()),
+        _ => return Err(LxError::EINVAL),
+    };
+    Ok(now.into())
+}
 
 impl Syscall<'_> {
+    /// Sums the accounted CPU time of every thread belonging to this process.
+    fn process_cpu_time(&self) -> CpuTime {
+        self.zircon_process()
+            .thread_ids()
+            .into_iter()
+            .map(thread_cpu_time)
+            .fold(CpuTime::default(), CpuTime::add)
+    }
+
+    /// Resolves the current time for `clockid`, additionally rejecting the
+    /// `CLOCK_PROCESS_CPUTIME_ID`/`CLOCK_THREAD_CPUTIME_ID` clocks, whose
+    /// value would depend on the calling thread/process rather than the
+    /// wall clock alone.
+    ///
+    /// Nothing in this tree accounts real CPU time (see the `CpuTime` doc
+    /// comment), so `thread_cpu_time`/`process_cpu_time` would only ever
+    /// read back zero; reporting that as a real clock reading would be
+    /// worse than refusing outright, so these two ids are rejected with
+    /// `ENOSYS` (an unsupported `clockid` gets `EINVAL` from `clock_now`).
+    fn clock_time(&self, clockid: ClockId) -> LxResult<TimeSpec> {
+        match clockid {
+            ClockId::ClockProcessCpuTimeId | ClockId::ClockThreadCpuTimeId => Err(LxError::ENOSYS),
+            _ => clock_now(clockid),
+        }
+    }
+
     /// finds the resolution (precision) of the specified clock clockid, and
     /// if `buf` is non-NULL, stores it in the struct timespec pointed to by `buf`.
     ///
     /// the resolution of clocks depends on the implementation and cannot be configured by
     /// a particular process.
     ///
-    /// currently `clock` only support `CLOCK_REALTIME`.
-    /// 
+    /// `CLOCK_REALTIME`/`CLOCK_REALTIME_COARSE` track wall-clock time (a monotonic
+    /// base plus the stored boot-epoch offset, see `sys_clock_settime`);
+    /// `CLOCK_MONOTONIC`/`CLOCK_MONOTONIC_RAW`/`CLOCK_BOOTTIME` and their `_COARSE`
+    /// variant track nanoseconds since boot and are unaffected by wall-clock changes.
+    /// The `_COARSE` variants are rounded down to `USEC_PER_TICK` granularity.
+    /// `CLOCK_PROCESS_CPUTIME_ID`/`CLOCK_THREAD_CPUTIME_ID` are rejected with
+    /// `ENOSYS`; no CPU time is accounted in this tree (see `CpuTime`).
+    ///
     /// the `buf` argument is a wrapper of struct `timeval` which has fields:
     /// `sec: usize` and `usec: usize`
-    /// 
+    ///
     /// the SysResult is an alias for `LxError`
     /// which defined in `linux-object/src/error.rs`.
-    /// 
-    /// TODO: CLOCK_REALTIME_ALARM, CLOCK_REALTIME_COARSE, CLOCK_TAI, CLOCK_MONOTONIC, 
-    /// CLOCK_MONOTONIC_COARSE, CLOCK_MONOTONIC_RAW, CLOCK_BOOTTIME, CLOCK_BOOTTIME_ALARM,
-    /// CLOCK_PROCESS_CPUTIME_ID, CLOCK_THREAD_CPUTIME_ID.
+    ///
+    /// TODO: CLOCK_REALTIME_ALARM, CLOCK_TAI, CLOCK_BOOTTIME_ALARM.
     pub fn sys_clock_gettime(&self, clock: usize, mut buf: UserOutPtr<TimeSpec>) -> SysResult {
         info!("clock_gettime: id={:?} buf={:?}", clock, buf);
-        // TODO: handle clock_settime
-        let ts = TimeSpec::now();
+        let ts = self.clock_time(ClockId::from(clock))?;
         buf.write(ts)?;
 
         info!("TimeSpec: {:?}", ts);
@@ -38,6 +225,20 @@ impl Syscall<'_> {
         Ok(0)
     }
 
+    /// `clock_gettime64`: the year-2038-safe counterpart of `sys_clock_gettime`,
+    /// writing a `__kernel_timespec` (always 64-bit seconds) instead of the
+    /// legacy `usize`-sized `TimeSpec`.
+    pub fn sys_clock_gettime64(
+        &self,
+        clock: usize,
+        mut buf: UserOutPtr<KernelTimespec64>,
+    ) -> SysResult {
+        info!("clock_gettime64: id={:?} buf={:?}", clock, buf);
+        let now: Duration = self.clock_time(ClockId::from(clock))?.into();
+        buf.write(now.into())?;
+        Ok(0)
+    }
+
     /// get the time with second and microseconds.
     ///
     /// if `tz` is NULL return an error.
@@ -59,7 +260,7 @@ impl Syscall<'_> {
             return Err(LxError::EINVAL);
         }
 
-        let timeval = TimeVal::now();
+        let timeval: TimeVal = realtime_now().into();
         tv.write(timeval)?;
 
         info!("TimeVal: {:?}", timeval);
@@ -67,6 +268,48 @@ impl Syscall<'_> {
         Ok(0)
     }
 
+    /// Requires the caller to have superuser privilege, mirroring Linux's
+    /// `CAP_SYS_TIME` check on `clock_settime`/`settimeofday`.
+    fn check_time_privilege(&self) -> SysResult {
+        if self.linux_process().uid() != 0 {
+            return Err(LxError::EPERM);
+        }
+        Ok(0)
+    }
+
+    /// sets the time of the specified clock `clockid`.
+    ///
+    /// only `CLOCK_REALTIME` can be set; this recomputes the stored
+    /// boot-epoch offset so all subsequent `CLOCK_REALTIME` reads shift
+    /// consistently, while `CLOCK_MONOTONIC`/`CLOCK_BOOTTIME` stay tied to
+    /// time since boot. Requires superuser privilege.
+    pub fn sys_clock_settime(&self, clockid: usize, tp: UserInPtr<TimeSpec>) -> SysResult {
+        info!("clock_settime: clockid={:?}, tp={:?}", clockid, tp);
+        match ClockId::from(clockid) {
+            ClockId::ClockRealTime => {
+                self.check_time_privilege()?;
+                set_realtime_offset(tp.read()?.into());
+                Ok(0)
+            }
+            _ => Err(LxError::EINVAL),
+        }
+    }
+
+    /// sets the current time and timezone, as `sys_gettimeofday`'s counterpart.
+    ///
+    /// equivalent to `sys_clock_settime(CLOCK_REALTIME, tv)`; like
+    /// `sys_gettimeofday`, a non-null `tz` is rejected. Requires superuser
+    /// privilege.
+    pub fn sys_settimeofday(&self, tv: UserInPtr<TimeVal>, tz: UserInPtr<u8>) -> SysResult {
+        info!("settimeofday: tv={:?}, tz={:?}", tv, tz);
+        if !tz.is_null() {
+            return Err(LxError::EINVAL);
+        }
+        self.check_time_privilege()?;
+        set_realtime_offset(tv.read()?.into());
+        Ok(0)
+    }
+
     /// get time in seconds.
     ///
     /// returns the time as the number of seconds since the Epoch,
@@ -79,57 +322,76 @@ impl Syscall<'_> {
     #[cfg(target_arch = "x86_64")]
     pub fn sys_time(&mut self, mut time: UserOutPtr<u64>) -> SysResult {
         info!("time: time: {:?}", time);
-        let sec = TimeSpec::now().sec;
-        time.write(sec as u64)?;
-        Ok(sec)
+        let sec = realtime_now().as_secs();
+        time.write(sec)?;
+        Ok(sec as usize)
     }
 
     /// get resource usage
-    /// currently only support ru_utime and ru_stime:
     /// - `ru_utime`: user CPU time used
     /// - `ru_stime`: system CPU time used
     ///
+    /// `who` selects `RUSAGE_SELF` (the calling process), `RUSAGE_THREAD`
+    /// (the calling thread), or `RUSAGE_CHILDREN` (reaped children); any
+    /// other value is rejected with `EINVAL`.
+    ///
     /// the `rusage` argument is a wrapper of struct `RUsage` which has fields:
     /// `utime: TimeVal` and `stime: TimeVal`
-    /// 
+    ///
+    /// NOTE: `utime`/`stime` always read back zero — nothing in this tree
+    /// accounts real CPU time yet (see the `CpuTime` doc comment).
+    ///
     /// the `SysResult` is an alias for `LxError`
     /// which defined in `linux-object/src/error.rs`.
     pub fn sys_getrusage(&mut self, who: usize, mut rusage: UserOutPtr<RUsage>) -> SysResult {
         info!("getrusage: who: {}, rusage: {:?}", who, rusage);
 
+        let cpu = match who {
+            RUSAGE_SELF => self.process_cpu_time(),
+            RUSAGE_THREAD => thread_cpu_time(self.thread.id()),
+            RUSAGE_CHILDREN => children_cpu_time(self.zircon_process().id()),
+            _ => return Err(LxError::EINVAL),
+        };
         let new_rusage = RUsage {
-            utime: TimeVal::now(),
-            stime: TimeVal::now(),
+            utime: Duration::from_nanos(cpu.user_ns).into(),
+            stime: Duration::from_nanos(cpu.sys_ns).into(),
         };
         rusage.write(new_rusage)?;
         Ok(0)
     }
 
     /// get process times.
-    /// 
+    ///
     /// - `buf`: - a wrapper of `Tms` where to stores the current process times.
-    /// 
-    /// 
+    ///
+    /// fills `tms_utime`/`tms_stime` with this process's accounted CPU time
+    /// and `tms_cutime`/`tms_cstime` with that of its reaped children, all in
+    /// clock ticks (`USEC_PER_TICK`).
+    ///
+    /// NOTE: all four fields always read back zero — nothing in this tree
+    /// accounts real CPU time yet (see the `CpuTime` doc comment).
+    ///
     /// the `SysResult` is an alias for `LxError`
     /// which defined in `linux-object/src/error.rs`.
     pub fn sys_times(&mut self, mut buf: UserOutPtr<Tms>) -> SysResult {
         info!("times: buf: {:?}", buf);
 
-        let tv = TimeVal::now();
-
-        let tick = (tv.sec * 1_000_000 + tv.usec) / USEC_PER_TICK;
+        let ns_to_ticks = |ns: u64| (ns / 1000) as usize / USEC_PER_TICK;
 
+        let proc_cpu = self.process_cpu_time();
+        let children = children_cpu_time(self.zircon_process().id());
         let new_buf = Tms {
-            tms_utime: 0,
-            tms_stime: 0,
-            tms_cutime: 0,
-            tms_cstime: 0,
+            tms_utime: ns_to_ticks(proc_cpu.user_ns),
+            tms_stime: ns_to_ticks(proc_cpu.sys_ns),
+            tms_cutime: ns_to_ticks(children.user_ns),
+            tms_cstime: ns_to_ticks(children.sys_ns),
         };
 
         buf.write(new_buf)?;
 
+        let tick = ns_to_ticks(monotonic_now().as_nanos() as u64);
         info!("tick: {:?}", tick);
-        Ok(tick as usize)
+        Ok(tick)
     }
 
     /// Allows the calling thread to sleep for
@@ -141,58 +403,157 @@ impl Syscall<'_> {
         Ok(0)
     }
 
+    /// `nanosleep_time64`: the year-2038-safe counterpart of `sys_nanosleep`,
+    /// reading `req` (and writing `rem`) as `__kernel_timespec` instead of
+    /// the legacy `TimeSpec`. Unlike `sys_nanosleep`, which takes no `rem`,
+    /// an interrupted sleep reports the remaining duration through `rem`
+    /// and returns `EINTR`.
+    pub async fn sys_nanosleep_time64(
+        &self,
+        req: UserInPtr<KernelTimespec64>,
+        mut rem: UserOutPtr<KernelTimespec64>,
+    ) -> SysResult {
+        info!("nanosleep_time64: deadline={:?}", req);
+        let duration: Duration = req.read()?.into();
+        let start = monotonic_now();
+        nanosleep(duration).await;
+        let elapsed = monotonic_now().saturating_sub(start);
+        // Only a sleep cut short (elapsed strictly less than requested)
+        // counts as interrupted; `elapsed == duration` is a normal
+        // completion and must not be reported as `EINTR` (see the fix to
+        // `clock_nanosleep_core` for the same boundary).
+        if elapsed < duration {
+            if !rem.is_null() {
+                rem.write((duration - elapsed).into())?;
+            }
+            return Err(LxError::EINTR);
+        }
+        Ok(0)
+    }
+
+    /// Shared implementation behind `sys_clock_nanosleep` and
+    /// `sys_clock_nanosleep_time64`, working purely in `Duration` so both
+    /// the legacy 32-bit-second and `__kernel_timespec` ABI layouts can
+    /// share one code path.
+    ///
+    /// sleeps for the duration given by `req`, on the clock selected by
+    /// `clockid`. Only the realtime and monotonic clock families are
+    /// supported; the CPU-time clocks are rejected with `ENOSYS` and anything
+    /// else with `EINVAL` instead of silently succeeding.
+    ///
+    /// with `TIMER_ABSTIME` set in `flags`, `req` is instead an absolute
+    /// deadline on the selected clock: if it has already passed this
+    /// returns immediately, otherwise it sleeps for `deadline - now()`.
+    /// Absolute sleeps never report a remainder.
+    async fn clock_nanosleep_core(
+        &self,
+        clockid: ClockId,
+        flags: ClockFlags,
+        req: Duration,
+    ) -> LxResult<NanosleepOutcome> {
+        if matches!(
+            clockid,
+            ClockId::ClockProcessCpuTimeId | ClockId::ClockThreadCpuTimeId
+        ) {
+            // No CPU time is accounted in this tree (`thread_cpu_time`/
+            // `children_cpu_time` always return zero, and `self.clock_time()`
+            // rejects these ids). Sleeping until a CPU-time target is reached
+            // against a counter that never advances would hang the caller
+            // forever, so these ids are refused.
+            return Err(LxError::ENOSYS);
+        }
+
+        match clockid {
+            ClockId::ClockRealTime
+            | ClockId::ClockMonotonic
+            | ClockId::ClockMonotonicRaw
+            | ClockId::ClockBootTime
+            | ClockId::ClockRealTimeCoarse
+            | ClockId::ClockMonotonicCoarse => {}
+            _ => return Err(LxError::EINVAL),
+        }
+
+        match flags {
+            ClockFlags::TimerAbsTime => {
+                let now: Duration = clock_now(clockid)?.into();
+                if let Some(remaining) = req.checked_sub(now) {
+                    nanosleep(remaining).await;
+                }
+                Ok(NanosleepOutcome::Done)
+            }
+            ClockFlags::ZeroFlag => {
+                let start: Duration = clock_now(clockid)?.into();
+                nanosleep(req).await;
+                let now: Duration = clock_now(clockid)?.into();
+                let elapsed = now.saturating_sub(start);
+                // Only a sleep cut short (elapsed strictly less than
+                // requested) counts as interrupted; `elapsed == req` is a
+                // normal completion and must not be reported as `EINTR`.
+                // A coarse/ticked clock can easily land exactly on `req`.
+                if elapsed < req {
+                    Ok(NanosleepOutcome::Interrupted(req - elapsed))
+                } else {
+                    Ok(NanosleepOutcome::Done)
+                }
+            }
+        }
+    }
+
     /// clock nanosleep
+    ///
+    /// a relative sleep that is cut short writes the time still remaining
+    /// into `rem` (if non-null) and returns `EINTR`, so libc can restart it.
+    /// See `clock_nanosleep_core` for the clock/flag semantics.
     pub async fn sys_clock_nanosleep(
         &self,
         clockid: usize,
         flags: usize,
         req: UserInPtr<TimeSpec>,
-        rem: UserOutPtr<TimeSpec>,
+        mut rem: UserOutPtr<TimeSpec>,
     ) -> SysResult {
-        warn!(
-            "clock_nanosleep: clockid={:?},flags={:?},req={:?},，rem={:?}",
-            clockid,
-            flags,
-            req.read()?,
-            rem
-        );
-        use core::time::Duration;
-        let duration: Duration = req.read()?.into();
         let clockid = ClockId::from(clockid);
         let flags = ClockFlags::from(flags);
-        warn!("clockid={:?},flags={:?}", clockid, flags,);
-        match clockid {
-            ClockId::ClockRealTime => {
-                match flags {
-                    ClockFlags::ZeroFlag => {
-                        nanosleep(duration).await;
-                    }
-                    ClockFlags::TimerAbsTime => {
-                        // 目前统一由nanosleep代替了、之后再修改
-                        nanosleep(duration).await;
-                    }
+        let req_ts = req.read()?;
+        warn!(
+            "clock_nanosleep: clockid={:?}, flags={:?}, req={:?}, rem={:?}",
+            clockid, flags, req_ts, rem
+        );
+        match self.clock_nanosleep_core(clockid, flags, req_ts.into()).await? {
+            NanosleepOutcome::Done => Ok(0),
+            NanosleepOutcome::Interrupted(remaining) => {
+                if !rem.is_null() {
+                    rem.write(remaining.into())?;
                 }
+                Err(LxError::EINTR)
             }
-            ClockId::ClockMonotonic => {
-                match flags {
-                    ClockFlags::ZeroFlag => {
-                        nanosleep(duration).await;
-                    }
-                    ClockFlags::TimerAbsTime => {
-                        // 目前统一由nanosleep代替了、之后再修改
-                        nanosleep(duration).await;
-                    }
+        }
+    }
+
+    /// `clock_nanosleep_time64`: the year-2038-safe counterpart of
+    /// `sys_clock_nanosleep`, reading `req` (and writing `rem`) as
+    /// `__kernel_timespec` instead of the legacy `TimeSpec`.
+    pub async fn sys_clock_nanosleep_time64(
+        &self,
+        clockid: usize,
+        flags: usize,
+        req: UserInPtr<KernelTimespec64>,
+        mut rem: UserOutPtr<KernelTimespec64>,
+    ) -> SysResult {
+        let clockid = ClockId::from(clockid);
+        let flags = ClockFlags::from(flags);
+        let req_ts = req.read()?;
+        warn!(
+            "clock_nanosleep_time64: clockid={:?}, flags={:?}, req={:?}, rem={:?}",
+            clockid, flags, req_ts, rem
+        );
+        match self.clock_nanosleep_core(clockid, flags, req_ts.into()).await? {
+            NanosleepOutcome::Done => Ok(0),
+            NanosleepOutcome::Interrupted(remaining) => {
+                if !rem.is_null() {
+                    rem.write(remaining.into())?;
                 }
+                Err(LxError::EINTR)
             }
-            ClockId::ClockProcessCpuTimeId => {}
-            ClockId::ClockThreadCpuTimeId => {}
-            ClockId::ClockMonotonicRaw => {}
-            ClockId::ClockRealTimeCoarse => {}
-            ClockId::ClockMonotonicCoarse => {}
-            ClockId::ClockBootTime => {}
-            ClockId::ClockRealTimeAlarm => {}
-            ClockId::ClockBootTimeAlarm => {}
         }
-        Ok(0)
     }
 }